@@ -96,6 +96,182 @@ impl Element {
         Comment(new_key)
     }
 
+    fn alloc_element(document: &mut Document, element: NewElement) -> Node {
+        let new_key = document
+            .items
+            .insert(ItemValue::Node(NodeValue::Element(ElementValue {
+                name: element.name,
+                children: vec![],
+            })));
+        document.attrs.insert(new_key, element.attrs);
+        Node::Element(Element(new_key))
+    }
+
+    fn alloc_text(document: &mut Document, text: &str) -> Node {
+        let new_key = document
+            .items
+            .insert(ItemValue::Node(NodeValue::Text(text.to_string())));
+        Node::Text(Text(new_key))
+    }
+
+    fn alloc_cdata(document: &mut Document, text: &str) -> Node {
+        let new_key = document
+            .items
+            .insert(ItemValue::Node(NodeValue::CData(text.to_string())));
+        Node::CDataSection(CDataSection(new_key))
+    }
+
+    fn alloc_comment(document: &mut Document, text: &str) -> Node {
+        let new_key = document
+            .items
+            .insert(ItemValue::Node(NodeValue::Comment(text.to_string())));
+        Node::Comment(Comment(new_key))
+    }
+
+    fn splice_child(self, document: &mut Document, node: Node, index: usize) {
+        document.parents.insert(node.as_key(), self);
+        document
+            .items
+            .get_mut(self.0)
+            .unwrap()
+            .as_element_mut()
+            .unwrap()
+            .children
+            .insert(index, node);
+    }
+
+    fn child_position(self, document: &Document, reference: Node) -> Option<usize> {
+        document
+            .items
+            .get(self.0)
+            .unwrap()
+            .as_element()
+            .unwrap()
+            .children
+            .iter()
+            .position(|x| x == &reference)
+    }
+
+    pub fn insert_before(
+        self,
+        document: &mut Document,
+        reference: Node,
+        new: NewElement,
+    ) -> Option<Element> {
+        let index = self.child_position(document, reference)?;
+        let node = Self::alloc_element(document, new);
+        self.splice_child(document, node, index);
+        Some(node.as_element().unwrap())
+    }
+
+    pub fn insert_after(
+        self,
+        document: &mut Document,
+        reference: Node,
+        new: NewElement,
+    ) -> Option<Element> {
+        let index = self.child_position(document, reference)? + 1;
+        let node = Self::alloc_element(document, new);
+        self.splice_child(document, node, index);
+        Some(node.as_element().unwrap())
+    }
+
+    pub fn prepend_new_element(self, document: &mut Document, new: NewElement) -> Element {
+        let node = Self::alloc_element(document, new);
+        self.splice_child(document, node, 0);
+        node.as_element().unwrap()
+    }
+
+    pub fn insert_text_before(
+        self,
+        document: &mut Document,
+        reference: Node,
+        text: &str,
+    ) -> Option<Text> {
+        let index = self.child_position(document, reference)?;
+        let node = Self::alloc_text(document, text);
+        self.splice_child(document, node, index);
+        Some(Text(node.as_key()))
+    }
+
+    pub fn insert_text_after(
+        self,
+        document: &mut Document,
+        reference: Node,
+        text: &str,
+    ) -> Option<Text> {
+        let index = self.child_position(document, reference)? + 1;
+        let node = Self::alloc_text(document, text);
+        self.splice_child(document, node, index);
+        Some(Text(node.as_key()))
+    }
+
+    pub fn prepend_text(self, document: &mut Document, text: &str) -> Text {
+        let node = Self::alloc_text(document, text);
+        self.splice_child(document, node, 0);
+        Text(node.as_key())
+    }
+
+    pub fn insert_cdata_before(
+        self,
+        document: &mut Document,
+        reference: Node,
+        text: &str,
+    ) -> Option<CDataSection> {
+        let index = self.child_position(document, reference)?;
+        let node = Self::alloc_cdata(document, text);
+        self.splice_child(document, node, index);
+        Some(CDataSection(node.as_key()))
+    }
+
+    pub fn insert_cdata_after(
+        self,
+        document: &mut Document,
+        reference: Node,
+        text: &str,
+    ) -> Option<CDataSection> {
+        let index = self.child_position(document, reference)? + 1;
+        let node = Self::alloc_cdata(document, text);
+        self.splice_child(document, node, index);
+        Some(CDataSection(node.as_key()))
+    }
+
+    pub fn prepend_cdata(self, document: &mut Document, text: &str) -> CDataSection {
+        let node = Self::alloc_cdata(document, text);
+        self.splice_child(document, node, 0);
+        CDataSection(node.as_key())
+    }
+
+    pub fn insert_comment_before(
+        self,
+        document: &mut Document,
+        reference: Node,
+        text: &str,
+    ) -> Option<Comment> {
+        let index = self.child_position(document, reference)?;
+        let node = Self::alloc_comment(document, text);
+        self.splice_child(document, node, index);
+        Some(Comment(node.as_key()))
+    }
+
+    pub fn insert_comment_after(
+        self,
+        document: &mut Document,
+        reference: Node,
+        text: &str,
+    ) -> Option<Comment> {
+        let index = self.child_position(document, reference)? + 1;
+        let node = Self::alloc_comment(document, text);
+        self.splice_child(document, node, index);
+        Some(Comment(node.as_key()))
+    }
+
+    pub fn prepend_comment(self, document: &mut Document, text: &str) -> Comment {
+        let node = Self::alloc_comment(document, text);
+        self.splice_child(document, node, 0);
+        Comment(node.as_key())
+    }
+
     pub fn remove_child(self, document: &mut Document, node: Node) {
         let element = document
             .items
@@ -109,7 +285,61 @@ impl Element {
             }
             None => return,
         }
-        document.items.remove(node.as_key());
+        free_subtree(document, node);
+    }
+
+    pub fn detach(self, document: &mut Document, node: Node) -> Node {
+        let element = document
+            .items
+            .get_mut(self.0)
+            .unwrap()
+            .as_element_mut()
+            .unwrap();
+        if let Some(i) = element.children.iter().position(|x| x == &node) {
+            element.children.remove(i);
+        }
+        document.parents.remove(node.as_key());
+        node
+    }
+
+    pub fn move_to(self, document: &mut Document, new_parent: Element) {
+        if let Some(parent) = self.parent(document) {
+            parent.detach(document, self.as_node());
+        }
+        document.parents.insert(self.0, new_parent);
+        document
+            .items
+            .get_mut(new_parent.0)
+            .unwrap()
+            .as_element_mut()
+            .unwrap()
+            .children
+            .push(self.as_node());
+    }
+
+    pub fn deep_clone(self, document: &mut Document) -> Element {
+        let name = self.name(document).to_string();
+        let attrs = self.attributes(document).clone();
+        let new_key = document
+            .items
+            .insert(ItemValue::Node(NodeValue::Element(ElementValue {
+                name,
+                children: vec![],
+            })));
+        document.attrs.insert(new_key, attrs);
+        let new_element = Element(new_key);
+
+        let children = self.children(document).to_vec();
+        for child in children {
+            let cloned = clone_node(document, child);
+            new_element.splice_child(
+                document,
+                cloned,
+                new_element.children(document).len(),
+            );
+        }
+
+        new_element
     }
 
     pub fn parent(self, document: &Document) -> Option<Element> {
@@ -157,7 +387,31 @@ impl Element {
     }
 
     pub fn walk<'d>(&self, doc: &'d Document) -> Box<dyn Iterator<Item = Element> + 'd> {
-        walk_tree(doc, *self)
+        let mut stack: Vec<(DocKey, usize)> = vec![(self.0, 0)];
+
+        Box::new(std::iter::from_fn(move || loop {
+            let &(key, index) = stack.last()?;
+
+            let children = match doc.items.get(key).and_then(|item| item.as_element()) {
+                Some(element) => &element.children,
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            };
+
+            if index >= children.len() {
+                stack.pop();
+                continue;
+            }
+
+            stack.last_mut().unwrap().1 += 1;
+
+            if let Some(child) = children[index].as_element() {
+                stack.push((child.0, 0));
+                return Some(child);
+            }
+        }))
     }
 
     pub fn next_sibling_element(&self, doc: &Document) -> Option<Element> {
@@ -221,35 +475,705 @@ impl Element {
             .filter(|x| selector.matches(doc, *x))
             .collect()
     }
-}
 
-fn walk_tree<'a>(doc: &'a Document, element: Element) -> Box<dyn Iterator<Item = Element> + 'a> {
-    let children = element.children(doc).to_vec();
-    let mut index = 0usize;
+    // Note: `@attr` steps cannot yield a value, as the crate has no attribute
+    // node type; they only filter to element nodes that carry the attribute,
+    // whose value can then be read with [`Element::attribute`].
+    pub fn xpath(&self, document: &Document, expr: &str) -> Result<Vec<Node>, XPathError> {
+        let (absolute, steps) = parse_xpath(expr)?;
 
-    let mut last_child: Option<Box<dyn Iterator<Item = Element>>> = None;
+        let mut current: Vec<Node> = if absolute {
+            vec![self.root(document).as_node()]
+        } else {
+            vec![self.as_node()]
+        };
 
-    Box::new(std::iter::from_fn(move || loop {
-        if let Some(iter) = last_child.as_mut() {
-            if let Some(next) = iter.next() {
-                return Some(next);
+        for (i, step) in steps.iter().enumerate() {
+            let axis = if absolute && i == 0 && matches!(step.axis, Axis::Child) {
+                Axis::SelfAxis
             } else {
-                last_child = None;
+                step.axis
+            };
+
+            let mut next = Vec::new();
+            for node in &current {
+                collect_step(document, axis, &step.test, *node, &mut next);
+            }
+            next = dedup_nodes(next);
+
+            for predicate in step.predicates.iter().filter(|p| !p.is_positional()) {
+                next = apply_predicate(document, predicate, next);
             }
+            for predicate in step.predicates.iter().filter(|p| p.is_positional()) {
+                next = apply_predicate(document, predicate, next);
+            }
+
+            current = next;
         }
 
-        if index >= children.len() {
-            return None;
+        Ok(current)
+    }
+
+    fn root(self, document: &Document) -> Element {
+        let mut current = self;
+        while let Some(parent) = current.parent(document) {
+            current = parent;
         }
+        current
+    }
 
-        let child = children[index];
-        index += 1;
+    pub fn text_content(&self, document: &Document) -> String {
+        let mut buf = String::new();
+        collect_text(document, self.as_node(), &mut buf);
+        buf
+    }
 
-        if let Some(child) = child.as_element() {
-            last_child = Some(Box::new(walk_tree(doc, child)));
-            return Some(child);
+    pub fn set_text_content(&self, document: &mut Document, text: &str) {
+        let children = self.children(document).to_vec();
+        for child in children {
+            self.remove_child(document, child);
         }
-    }))
+        self.append_text(document, text);
+    }
+
+    pub fn local_name<'d>(&self, document: &'d Document) -> &'d str {
+        split_qname(self.name(document)).1
+    }
+
+    pub fn prefix<'d>(&self, document: &'d Document) -> Option<&'d str> {
+        split_qname(self.name(document)).0
+    }
+
+    pub fn namespace_uri(&self, document: &Document) -> Option<String> {
+        let prefix = self.prefix(document);
+        self.resolve_namespace(document, prefix)
+    }
+
+    pub fn attribute_prefix<'a>(&self, name: &'a str) -> Option<&'a str> {
+        split_qname(name).0
+    }
+
+    pub fn attribute_local_name<'a>(&self, name: &'a str) -> &'a str {
+        split_qname(name).1
+    }
+
+    pub fn attribute_namespace_uri(&self, document: &Document, name: &str) -> Option<String> {
+        // Unprefixed attributes are namespace-less per the XML Namespaces
+        // spec; the default namespace never applies to them.
+        let prefix = split_qname(name).0?;
+        self.resolve_namespace(document, Some(prefix))
+    }
+
+    fn resolve_namespace(&self, document: &Document, prefix: Option<&str>) -> Option<String> {
+        let key = match prefix {
+            Some(prefix) => format!("xmlns:{}", prefix),
+            None => "xmlns".to_string(),
+        };
+
+        let mut current = Some(*self);
+        while let Some(element) = current {
+            if let Some(uri) = element.attribute(document, &key) {
+                return Some(uri.to_string());
+            }
+            current = element.parent(document);
+        }
+
+        None
+    }
+
+    pub fn set_namespace(&self, document: &mut Document, prefix: Option<&str>, uri: &str) {
+        let key = match prefix {
+            Some(prefix) => format!("xmlns:{}", prefix),
+            None => "xmlns".to_string(),
+        };
+        self.set_attribute(document, &key, uri);
+    }
+
+    pub fn closest(&self, doc: &Document, selector: &Selector) -> Option<Element> {
+        let mut current = Some(*self);
+        while let Some(element) = current {
+            if selector.matches(doc, element) {
+                return Some(element);
+            }
+            current = element.parent(doc);
+        }
+        None
+    }
+
+    pub fn ancestors<'d>(&self, doc: &'d Document) -> Box<dyn Iterator<Item = Element> + 'd> {
+        let mut current = self.parent(doc);
+        Box::new(std::iter::from_fn(move || {
+            let element = current?;
+            current = element.parent(doc);
+            Some(element)
+        }))
+    }
+
+    pub fn following_siblings<'d>(&self, doc: &'d Document) -> Box<dyn Iterator<Item = Element> + 'd> {
+        let parent = match self.parent(doc) {
+            Some(v) => v,
+            None => return Box::new(std::iter::empty()),
+        };
+
+        let children = parent.children(doc).to_vec();
+        let mut index = children
+            .iter()
+            .position(|x| x == &self.as_node())
+            .map(|i| i + 1)
+            .unwrap_or(children.len());
+
+        Box::new(std::iter::from_fn(move || {
+            while index < children.len() {
+                let child = children[index];
+                index += 1;
+                if let Some(sibling) = child.as_element() {
+                    return Some(sibling);
+                }
+            }
+            None
+        }))
+    }
+
+    pub fn preceding_siblings<'d>(&self, doc: &'d Document) -> Box<dyn Iterator<Item = Element> + 'd> {
+        let parent = match self.parent(doc) {
+            Some(v) => v,
+            None => return Box::new(std::iter::empty()),
+        };
+
+        let children = parent.children(doc).to_vec();
+        let mut index = children
+            .iter()
+            .position(|x| x == &self.as_node())
+            .unwrap_or(0);
+
+        Box::new(std::iter::from_fn(move || {
+            while index > 0 {
+                index -= 1;
+                if let Some(sibling) = children[index].as_element() {
+                    return Some(sibling);
+                }
+            }
+            None
+        }))
+    }
+
+    // Namespace-qualified queries are offered as a dedicated entry point
+    // rather than through the CSS `Selector` grammar, which has no notion of
+    // namespace prefixes: the resolution happens here against the live
+    // `xmlns` bindings via `namespace_uri`, matching by resolved URI and local
+    // name rather than by the opaque prefixed string a `Selector` would see.
+    pub fn query_selector_ns(
+        &self,
+        document: &Document,
+        namespace_uri: &str,
+        local_name: &str,
+    ) -> Vec<Element> {
+        self.walk(document)
+            .filter(|element| {
+                element.local_name(document) == local_name
+                    && element.namespace_uri(document).as_deref() == Some(namespace_uri)
+            })
+            .collect()
+    }
+}
+
+fn free_subtree(document: &mut Document, node: Node) {
+    if let Node::Element(element) = node {
+        let children = element.children(document).to_vec();
+        for child in children {
+            free_subtree(document, child);
+        }
+    }
+    document.parents.remove(node.as_key());
+    document.items.remove(node.as_key());
+}
+
+fn collect_text(document: &Document, node: Node, buf: &mut String) {
+    match node {
+        Node::Element(element) => {
+            for child in element.children(document) {
+                collect_text(document, *child, buf);
+            }
+        }
+        Node::Text(text) => {
+            if let Some(ItemValue::Node(NodeValue::Text(s))) = document.items.get(text.0) {
+                buf.push_str(s);
+            }
+        }
+        Node::CDataSection(cdata) => {
+            if let Some(ItemValue::Node(NodeValue::CData(s))) = document.items.get(cdata.0) {
+                buf.push_str(s);
+            }
+        }
+        Node::Comment(_) => {}
+    }
+}
+
+fn clone_node(document: &mut Document, node: Node) -> Node {
+    match node {
+        Node::Element(element) => Node::Element(element.deep_clone(document)),
+        Node::Text(text) => {
+            let value = match document.items.get(text.0) {
+                Some(ItemValue::Node(NodeValue::Text(s))) => s.clone(),
+                _ => String::new(),
+            };
+            Element::alloc_text(document, &value)
+        }
+        Node::CDataSection(cdata) => {
+            let value = match document.items.get(cdata.0) {
+                Some(ItemValue::Node(NodeValue::CData(s))) => s.clone(),
+                _ => String::new(),
+            };
+            Element::alloc_cdata(document, &value)
+        }
+        Node::Comment(comment) => {
+            let value = match document.items.get(comment.0) {
+                Some(ItemValue::Node(NodeValue::Comment(s))) => s.clone(),
+                _ => String::new(),
+            };
+            Element::alloc_comment(document, &value)
+        }
+    }
+}
+
+fn split_qname(name: &str) -> (Option<&str>, &str) {
+    match name.split_once(':') {
+        Some((prefix, local)) => (Some(prefix), local),
+        None => (None, name),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XPathError(pub String);
+
+impl std::fmt::Display for XPathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid xpath expression: {}", self.0)
+    }
+}
+
+impl std::error::Error for XPathError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    SelfAxis,
+    Parent,
+    Child,
+    DescendantOrSelf,
+    Attribute,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NodeTest {
+    Name(String),
+    Wildcard,
+    Text,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Predicate {
+    HasAttr(String),
+    AttrEquals(String, String),
+    Position(usize),
+}
+
+impl Predicate {
+    fn is_positional(&self) -> bool {
+        matches!(self, Predicate::Position(_))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Step {
+    axis: Axis,
+    test: NodeTest,
+    predicates: Vec<Predicate>,
+}
+
+fn parse_xpath(expr: &str) -> Result<(bool, Vec<Step>), XPathError> {
+    let expr = expr.trim();
+    if expr.is_empty() {
+        return Err(XPathError("empty expression".to_string()));
+    }
+
+    let absolute = expr.starts_with('/');
+
+    let bytes = expr.as_bytes();
+    let mut segments: Vec<(bool, &str)> = Vec::new();
+    let mut start = 0;
+    let mut descendant = false;
+    let mut i = 0;
+    let mut depth = 0i32;
+    let mut quote: Option<u8> = None;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match quote {
+            Some(q) => {
+                if b == q {
+                    quote = None;
+                }
+                i += 1;
+            }
+            None => match b {
+                b'\'' | b'"' => {
+                    quote = Some(b);
+                    i += 1;
+                }
+                b'[' => {
+                    depth += 1;
+                    i += 1;
+                }
+                b']' => {
+                    depth -= 1;
+                    i += 1;
+                }
+                b'/' if depth == 0 => {
+                    let seg = &expr[start..i];
+                    if !seg.is_empty() {
+                        segments.push((descendant, seg));
+                        descendant = false;
+                    }
+                    if i + 1 < bytes.len() && bytes[i + 1] == b'/' {
+                        descendant = true;
+                        i += 2;
+                    } else {
+                        i += 1;
+                    }
+                    start = i;
+                }
+                _ => {
+                    i += 1;
+                }
+            },
+        }
+    }
+    let seg = &expr[start..];
+    if !seg.is_empty() {
+        segments.push((descendant, seg));
+    } else if descendant {
+        return Err(XPathError("trailing '//' with no step".to_string()));
+    }
+
+    if segments.is_empty() {
+        return Err(XPathError("no location steps".to_string()));
+    }
+
+    segments
+        .into_iter()
+        .map(|(descendant, seg)| parse_step(descendant, seg))
+        .collect::<Result<Vec<_>, _>>()
+        .map(|steps| (absolute, steps))
+}
+
+fn parse_step(descendant: bool, seg: &str) -> Result<Step, XPathError> {
+    let (main, predicates) = match seg.find('[') {
+        Some(idx) => {
+            if !seg.ends_with(']') {
+                return Err(XPathError(format!("malformed predicate in step: {}", seg)));
+            }
+            (&seg[..idx], &seg[idx..])
+        }
+        None => (seg, ""),
+    };
+
+    let predicates = parse_predicates(predicates)?;
+
+    let (axis, test) = if main == "." {
+        (Axis::SelfAxis, NodeTest::Wildcard)
+    } else if main == ".." {
+        (Axis::Parent, NodeTest::Wildcard)
+    } else if let Some(attr) = main.strip_prefix('@') {
+        if attr.is_empty() {
+            return Err(XPathError("empty attribute name".to_string()));
+        }
+        (Axis::Attribute, NodeTest::Name(attr.to_string()))
+    } else if main == "*" {
+        (descendant_axis(descendant), NodeTest::Wildcard)
+    } else if main == "text()" {
+        (descendant_axis(descendant), NodeTest::Text)
+    } else if main.is_empty() {
+        return Err(XPathError("empty location step".to_string()));
+    } else {
+        (descendant_axis(descendant), NodeTest::Name(main.to_string()))
+    };
+
+    Ok(Step {
+        axis,
+        test,
+        predicates,
+    })
+}
+
+fn descendant_axis(descendant: bool) -> Axis {
+    if descendant {
+        Axis::DescendantOrSelf
+    } else {
+        Axis::Child
+    }
+}
+
+fn parse_predicates(mut rest: &str) -> Result<Vec<Predicate>, XPathError> {
+    let mut predicates = Vec::new();
+    while !rest.is_empty() {
+        if !rest.starts_with('[') {
+            return Err(XPathError(format!("expected predicate, found: {}", rest)));
+        }
+        let end = rest
+            .find(']')
+            .ok_or_else(|| XPathError("unterminated predicate".to_string()))?;
+        let inner = &rest[1..end];
+        predicates.push(parse_predicate(inner)?);
+        rest = &rest[end + 1..];
+    }
+    Ok(predicates)
+}
+
+fn parse_predicate(inner: &str) -> Result<Predicate, XPathError> {
+    let inner = inner.trim();
+    if inner.is_empty() {
+        return Err(XPathError("empty predicate".to_string()));
+    }
+
+    if let Ok(n) = inner.parse::<usize>() {
+        if n == 0 {
+            return Err(XPathError("positional predicate is 1-based".to_string()));
+        }
+        return Ok(Predicate::Position(n));
+    }
+
+    if let Some(attr) = inner.strip_prefix('@') {
+        if let Some(eq) = attr.find('=') {
+            let name = attr[..eq].trim();
+            let value = attr[eq + 1..].trim();
+            let value = unquote(value)
+                .ok_or_else(|| XPathError(format!("expected quoted value: {}", value)))?;
+            if name.is_empty() {
+                return Err(XPathError("empty attribute name".to_string()));
+            }
+            return Ok(Predicate::AttrEquals(name.to_string(), value.to_string()));
+        }
+        let name = attr.trim();
+        if name.is_empty() {
+            return Err(XPathError("empty attribute name".to_string()));
+        }
+        return Ok(Predicate::HasAttr(name.to_string()));
+    }
+
+    Err(XPathError(format!("unsupported predicate: {}", inner)))
+}
+
+fn unquote(value: &str) -> Option<&str> {
+    value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+}
+
+fn collect_step(document: &Document, axis: Axis, test: &NodeTest, node: Node, out: &mut Vec<Node>) {
+    match axis {
+        Axis::SelfAxis => {
+            if node_matches(document, test, node) {
+                out.push(node);
+            }
+        }
+        Axis::Child => {
+            if let Node::Element(element) = node {
+                for child in element.children(document) {
+                    if node_matches(document, test, *child) {
+                        out.push(*child);
+                    }
+                }
+            }
+        }
+        Axis::DescendantOrSelf => {
+            if node_matches(document, test, node) {
+                out.push(node);
+            }
+            if let Node::Element(element) = node {
+                // `walk` yields descendants only, so the context node's own
+                // direct text children must be collected before we descend.
+                if let NodeTest::Text = test {
+                    for child in element.children(document) {
+                        if node_matches(document, test, *child) {
+                            out.push(*child);
+                        }
+                    }
+                }
+                for descendant in element.walk(document) {
+                    let descendant_node = descendant.as_node();
+                    if node_matches(document, test, descendant_node) {
+                        out.push(descendant_node);
+                    }
+                    if let NodeTest::Text = test {
+                        for child in descendant.children(document) {
+                            if node_matches(document, test, *child) {
+                                out.push(*child);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Axis::Parent => {
+            if let Node::Element(element) = node {
+                if let Some(parent) = element.parent(document) {
+                    let parent_node = parent.as_node();
+                    if node_matches(document, test, parent_node) {
+                        out.push(parent_node);
+                    }
+                }
+            }
+        }
+        Axis::Attribute => {
+            if let (Node::Element(element), NodeTest::Name(name)) = (node, test) {
+                if element.attribute(document, name).is_some() {
+                    out.push(node);
+                }
+            }
+        }
+    }
+}
+
+fn node_matches(document: &Document, test: &NodeTest, node: Node) -> bool {
+    match test {
+        NodeTest::Wildcard => matches!(node, Node::Element(_)),
+        NodeTest::Name(name) => match node {
+            Node::Element(element) => element.name(document) == name.as_str(),
+            _ => false,
+        },
+        NodeTest::Text => matches!(node, Node::Text(_) | Node::CDataSection(_)),
+    }
+}
+
+fn apply_predicate(document: &Document, predicate: &Predicate, nodes: Vec<Node>) -> Vec<Node> {
+    match predicate {
+        Predicate::Position(n) => nodes.into_iter().nth(n - 1).into_iter().collect(),
+        Predicate::HasAttr(name) => nodes
+            .into_iter()
+            .filter(|node| match node {
+                Node::Element(element) => element.attribute(document, name).is_some(),
+                _ => false,
+            })
+            .collect(),
+        Predicate::AttrEquals(name, value) => nodes
+            .into_iter()
+            .filter(|node| match node {
+                Node::Element(element) => element.attribute(document, name) == Some(value.as_str()),
+                _ => false,
+            })
+            .collect(),
+    }
+}
+
+fn dedup_nodes(nodes: Vec<Node>) -> Vec<Node> {
+    let mut out: Vec<Node> = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        if !out.contains(&node) {
+            out.push(node);
+        }
+    }
+    out
 }
 
 static EMPTY_INDEXMAP: Lazy<IndexMap<String, String>> = Lazy::new(IndexMap::new);
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::document::Document;
+
+    fn doc(xml: &str) -> Document {
+        Document::from_str(xml).unwrap()
+    }
+
+    #[test]
+    fn xpath_relative_and_absolute_child_paths() {
+        let doc = doc("<root><a><b/></a></root>");
+        let root = doc.root();
+
+        assert_eq!(root.xpath(&doc, "a/b").unwrap().len(), 1);
+        assert_eq!(root.xpath(&doc, "/root/a").unwrap().len(), 1);
+        assert!(root.xpath(&doc, "/nope/a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn xpath_descendant_wildcard_and_predicates() {
+        let doc = doc(r#"<root><item id="1"/><item id="2"/><other/></root>"#);
+        let root = doc.root();
+
+        assert_eq!(root.xpath(&doc, "//item").unwrap().len(), 2);
+        assert_eq!(root.xpath(&doc, "*").unwrap().len(), 3);
+        assert_eq!(root.xpath(&doc, "item[@id='2']").unwrap().len(), 1);
+        assert_eq!(root.xpath(&doc, "item[@id]").unwrap().len(), 2);
+        assert_eq!(root.xpath(&doc, "item[1]").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn xpath_parent_and_self_steps() {
+        let doc = doc("<root><a><b/></a></root>");
+        let root = doc.root();
+        let a = root.xpath(&doc, "a").unwrap()[0].as_element().unwrap();
+
+        assert_eq!(a.xpath(&doc, ".").unwrap(), vec![a.as_node()]);
+        assert_eq!(a.xpath(&doc, "..").unwrap(), vec![root.as_node()]);
+    }
+
+    #[test]
+    fn xpath_predicate_value_may_contain_slash() {
+        let doc = doc(r#"<root><a href="/foo/bar"/><a href="other"/></root>"#);
+        let root = doc.root();
+
+        assert_eq!(root.xpath(&doc, "a[@href='/foo/bar']").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn xpath_text_includes_direct_children() {
+        let doc = doc("<root>A<child>B</child></root>");
+        let root = doc.root();
+
+        // Both the direct text "A" and the descendant text "B" are returned.
+        assert_eq!(root.xpath(&doc, "//text()").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn xpath_invalid_expression_is_err() {
+        let doc = doc("<root/>");
+        let root = doc.root();
+
+        assert!(root.xpath(&doc, "").is_err());
+        assert!(root.xpath(&doc, "a[@id=unquoted]").is_err());
+        assert!(root.xpath(&doc, "a[0]").is_err());
+    }
+
+    #[test]
+    fn text_content_concatenates_in_document_order() {
+        let doc = doc("<root>A<child>B</child>C<!-- skip --></root>");
+        let root = doc.root();
+
+        assert_eq!(root.text_content(&doc), "ABC");
+    }
+
+    #[test]
+    fn set_text_content_replaces_children() {
+        let mut doc = doc("<root><a/><b/></root>");
+        let root = doc.root();
+
+        root.set_text_content(&mut doc, "hello");
+
+        assert_eq!(root.text_content(&doc), "hello");
+        assert_eq!(root.xpath(&doc, "*").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn deep_clone_duplicates_subtree() {
+        let mut doc = doc("<root><a>x</a></root>");
+        let root = doc.root();
+
+        let clone = root.deep_clone(&mut doc);
+
+        assert_ne!(clone, root);
+        assert_eq!(clone.text_content(&doc), root.text_content(&doc));
+        assert_eq!(clone.xpath(&doc, "a").unwrap().len(), 1);
+    }
+}